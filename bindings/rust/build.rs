@@ -0,0 +1,21 @@
+fn main() {
+    let src_dir = std::path::Path::new("src");
+
+    let mut c_config = cc::Build::new();
+    c_config.include(src_dir);
+    c_config
+        .flag_if_supported("-Wno-unused-parameter")
+        .flag_if_supported("-Wno-unused-but-set-variable")
+        .flag_if_supported("-Wno-trigraphs");
+
+    let parser_path = src_dir.join("parser.c");
+    c_config.file(&parser_path);
+
+    let scanner_path = src_dir.join("scanner.c");
+    c_config.file(&scanner_path);
+
+    c_config.compile("tree-sitter-notebook");
+
+    println!("cargo:rerun-if-changed={}", parser_path.display());
+    println!("cargo:rerun-if-changed={}", scanner_path.display());
+}
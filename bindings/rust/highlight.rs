@@ -0,0 +1,220 @@
+//! HTML/annotated-span rendering for notebook sources.
+//!
+//! Gated behind the `highlight` feature so consumers that only need parsing
+//! (e.g. the Nothelix plugin itself, which talks to Helix's own highlighter)
+//! don't pay for this.
+
+use std::ops::Range;
+
+use tree_sitter::{Language, Parser, Query, QueryCursor};
+
+use crate::{language, Cell, CellKind};
+
+/// A highlighted span of source text.
+///
+/// `captures` holds the single capture name that applies to this span.
+/// Overlapping captures (e.g. the host grammar's blanket `(cell_body)
+/// @none` around an injected language's own `keyword`/`string` captures)
+/// are resolved innermost-wins: only the narrowest containing capture
+/// survives, so a broader ancestor capture never leaks into the render.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighlightSpan {
+    pub byte_range: Range<usize>,
+    pub captures: Vec<String>,
+}
+
+/// Looks up the `Language` and `highlights.scm` to use for a code cell's
+/// declared language tag (e.g. `"python"` -> a bundled `tree-sitter-python`
+/// grammar and its highlight query). The notebook grammar only highlights
+/// its own delimiters and tags; which other grammars are even available to
+/// link against is a decision for the embedder, not this crate.
+pub trait InjectionResolver {
+    fn resolve(&self, language_tag: &str) -> Option<(Language, &str)>;
+}
+
+impl<F> InjectionResolver for F
+where
+    F: Fn(&str) -> Option<(Language, &'static str)>,
+{
+    fn resolve(&self, language_tag: &str) -> Option<(Language, &str)> {
+        self(language_tag)
+    }
+}
+
+/// No injected languages available; code cells are highlighted only as far
+/// as the notebook grammar itself goes.
+struct NoInjections;
+
+impl InjectionResolver for NoInjections {
+    fn resolve(&self, _language_tag: &str) -> Option<(Language, &str)> {
+        None
+    }
+}
+
+/// Highlight `source`, without recursing into injected cell languages.
+pub fn highlight(source: &str) -> Vec<HighlightSpan> {
+    highlight_with(source, &NoInjections)
+}
+
+/// Highlight `source`, recursing into an injected language's own highlight
+/// query (via `resolver`) for code cell bodies.
+pub fn highlight_with(source: &str, resolver: &dyn InjectionResolver) -> Vec<HighlightSpan> {
+    let mut raw = collect_captures(language(), crate::HIGHLIGHTS_QUERY, source, 0);
+
+    for cell in crate::cells(source) {
+        if cell.kind != CellKind::Code {
+            continue;
+        }
+        let Some(tag) = cell.language.as_deref() else {
+            continue;
+        };
+        let Some((injected_language, injected_highlights)) = resolver.resolve(tag) else {
+            continue;
+        };
+        let body_offset = body_offset(&cell);
+        raw.extend(collect_captures(
+            injected_language,
+            injected_highlights,
+            &cell.source,
+            body_offset,
+        ));
+    }
+
+    resolve_overlaps(raw)
+}
+
+/// Render `source` to a string of `<span class="...">` elements.
+pub fn to_html(source: &str) -> String {
+    to_html_with(source, &NoInjections)
+}
+
+/// Same as [`to_html`], but recursing into injected languages via `resolver`.
+pub fn to_html_with(source: &str, resolver: &dyn InjectionResolver) -> String {
+    let spans = highlight_with(source, resolver);
+    let mut out = String::with_capacity(source.len());
+    let mut pos = 0;
+    for span in spans {
+        if span.byte_range.start > pos {
+            out.push_str(&escape_html(&source[pos..span.byte_range.start]));
+        }
+        let class = span.captures.join(" ");
+        out.push_str(&format!("<span class=\"{class}\">"));
+        out.push_str(&escape_html(&source[span.byte_range.clone()]));
+        out.push_str("</span>");
+        pos = span.byte_range.end;
+    }
+    if pos < source.len() {
+        out.push_str(&escape_html(&source[pos..]));
+    }
+    out
+}
+
+/// The byte offset of `cell.source` within the *original* notebook source,
+/// so spans produced while highlighting an injected language (which only
+/// sees the cell's own body) can be translated back to document-absolute
+/// offsets.
+fn body_offset(cell: &Cell) -> usize {
+    cell.byte_range.end - cell.source.len()
+}
+
+fn collect_captures(
+    language: Language,
+    query_source: &str,
+    text: &str,
+    offset: usize,
+) -> Vec<(Range<usize>, String)> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(language)
+        .expect("Error loading highlight grammar");
+    let Some(tree) = parser.parse(text, None) else {
+        return Vec::new();
+    };
+    let query = match Query::new(language, query_source) {
+        Ok(query) => query,
+        Err(_) => return Vec::new(),
+    };
+
+    let bytes = text.as_bytes();
+    let mut cursor = QueryCursor::new();
+    let mut out = Vec::new();
+    for m in cursor.matches(&query, tree.root_node(), bytes) {
+        for capture in m.captures {
+            let name = query.capture_names()[capture.index as usize].clone();
+            let range = capture.node.byte_range();
+            out.push((offset + range.start..offset + range.end, name));
+        }
+    }
+    out
+}
+
+/// Flatten possibly-nested `(range, capture_name)` pairs into non-overlapping
+/// spans where, for each span, only the most specific (smallest, most
+/// deeply nested) capture that contains it survives — broader ancestor
+/// captures that also contain the span are discarded rather than combined.
+fn resolve_overlaps(raw: Vec<(Range<usize>, String)>) -> Vec<HighlightSpan> {
+    let mut boundaries: Vec<usize> = raw.iter().flat_map(|(r, _)| [r.start, r.end]).collect();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut spans = Vec::new();
+    for window in boundaries.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        if start == end {
+            continue;
+        }
+        let innermost = raw
+            .iter()
+            .filter(|(r, _)| r.start <= start && end <= r.end)
+            .min_by_key(|(r, _)| r.end - r.start);
+        let Some((_, name)) = innermost else {
+            continue;
+        };
+        spans.push(HighlightSpan {
+            byte_range: start..end,
+            captures: vec![name.clone()],
+        });
+    }
+    spans
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_html_wraps_captures_in_spans() {
+        let source = "%%code python\nprint(1)\n";
+        let html = to_html(source);
+        assert!(html.contains("<span class=\"keyword\">"));
+        assert!(html.contains("print(1)"));
+    }
+
+    #[test]
+    fn test_to_html_escapes_reserved_characters() {
+        let html = to_html("%%raw\n<a & b>\n");
+        assert!(html.contains("&lt;a &amp; b&gt;"));
+        assert!(!html.contains("<a & b>"));
+    }
+
+    #[test]
+    fn test_resolve_overlaps_keeps_only_the_narrowest_containing_capture() {
+        let raw = vec![
+            (0..10, "none".to_owned()),
+            (2..6, "keyword".to_owned()),
+        ];
+        let spans = resolve_overlaps(raw);
+
+        let overlap = spans
+            .iter()
+            .find(|s| s.byte_range == (2..6))
+            .expect("span covering the inner capture's range");
+        assert_eq!(overlap.captures, vec!["keyword".to_owned()]);
+    }
+}
@@ -3,12 +3,22 @@
 //! This crate provides Notebook language support for the tree-sitter parsing library.
 //! It's designed for use with the Nothelix plugin for Helix editor.
 
-use tree_sitter::Language;
+use std::ops::Range;
+
+use tree_sitter::{Language, Parser, Query, QueryCursor};
 
 extern "C" {
     fn tree_sitter_notebook() -> Language;
 }
 
+#[cfg(feature = "highlight")]
+mod highlight;
+#[cfg(feature = "highlight")]
+pub use highlight::{highlight, highlight_with, to_html, to_html_with, HighlightSpan, InjectionResolver};
+
+mod tags;
+pub use tags::{symbols, symbols_with, Symbol, SymbolKind, TagsResolver};
+
 /// Get the tree-sitter Language for Notebook files.
 ///
 /// # Example
@@ -35,6 +45,108 @@ pub const INJECTIONS_QUERY: &str = include_str!("../../queries/injections.scm");
 /// The text objects query for this language.
 pub const TEXTOBJECTS_QUERY: &str = include_str!("../../queries/textobjects.scm");
 
+/// The tags (symbol-outline) query for this language.
+pub const TAGS_QUERY: &str = include_str!("../../queries/tags.scm");
+
+/// The query backing [`cells`].
+const CELLS_QUERY: &str = include_str!("../../queries/cells.scm");
+
+/// The kind of a notebook [`Cell`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellKind {
+    Code,
+    Markdown,
+    Raw,
+}
+
+impl CellKind {
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "code" => Some(Self::Code),
+            "markdown" => Some(Self::Markdown),
+            "raw" => Some(Self::Raw),
+            _ => None,
+        }
+    }
+}
+
+/// A single notebook cell, sliced out of the original source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    pub kind: CellKind,
+    /// The language tag on the cell's delimiter line, e.g. `python`, `sql`.
+    /// Absent when the delimiter carries no tag; a tag on a markdown or raw
+    /// cell is reported as well.
+    pub language: Option<String>,
+    pub source: String,
+    pub byte_range: Range<usize>,
+}
+
+/// Parse `source` as a notebook and return its cells in document order.
+///
+/// Cells are extracted with a query over the parsed tree rather than by
+/// re-implementing tree walking in every downstream consumer. A cell with
+/// an unrecognised `cell_kind` (e.g. from a malformed or partially-typed
+/// delimiter) is skipped so that the cells after it are still returned.
+pub fn cells(source: &str) -> Vec<Cell> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(language())
+        .expect("Error loading Notebook grammar");
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+
+    let query = Query::new(language(), CELLS_QUERY).expect("cells.scm should be a valid query");
+    let kind_idx = query.capture_index_for_name("cell.kind").unwrap();
+    let language_idx = query.capture_index_for_name("cell.language").unwrap();
+    let body_idx = query.capture_index_for_name("cell.body").unwrap();
+    let node_idx = query.capture_index_for_name("cell.node").unwrap();
+
+    let bytes = source.as_bytes();
+    let mut cursor = QueryCursor::new();
+    let mut out = Vec::new();
+    for m in cursor.matches(&query, tree.root_node(), bytes) {
+        let Some(kind_capture) = m.captures.iter().find(|c| c.index == kind_idx) else {
+            continue;
+        };
+        let Ok(kind_text) = kind_capture.node.utf8_text(bytes) else {
+            continue;
+        };
+        let Some(kind) = CellKind::from_tag(kind_text) else {
+            continue;
+        };
+
+        let language = m
+            .captures
+            .iter()
+            .find(|c| c.index == language_idx)
+            .and_then(|c| c.node.utf8_text(bytes).ok())
+            .map(str::to_owned);
+
+        let Some(node_capture) = m.captures.iter().find(|c| c.index == node_idx) else {
+            continue;
+        };
+        let byte_range = node_capture.node.byte_range();
+
+        let source_text = m
+            .captures
+            .iter()
+            .find(|c| c.index == body_idx)
+            .and_then(|c| c.node.utf8_text(bytes).ok())
+            .unwrap_or("")
+            .to_owned();
+
+        out.push(Cell {
+            kind,
+            language,
+            source: source_text,
+            byte_range,
+        });
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,4 +156,24 @@ mod tests {
         let language = language();
         assert_eq!(language.version(), tree_sitter::LANGUAGE_VERSION);
     }
+
+    #[test]
+    fn test_cells_extracts_kind_and_language() {
+        let source = "%%markdown\nintro\n\n%%code python\nimport sys\n";
+        let cells = cells(source);
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[0].kind, CellKind::Markdown);
+        assert_eq!(cells[0].language, None);
+        assert_eq!(cells[1].kind, CellKind::Code);
+        assert_eq!(cells[1].language.as_deref(), Some("python"));
+    }
+
+    #[test]
+    fn test_cells_skips_malformed_cell_but_keeps_later_ones() {
+        let source = "%%bogus\nwhatever\n\n%%code bash\necho hi\n";
+        let cells = cells(source);
+        assert_eq!(cells.len(), 1);
+        assert_eq!(cells[0].kind, CellKind::Code);
+        assert_eq!(cells[0].language.as_deref(), Some("bash"));
+    }
 }
@@ -0,0 +1,217 @@
+//! Symbol-outline extraction for notebook navigation (goto-symbol,
+//! document outline).
+
+use std::ops::Range;
+
+use tree_sitter::{Language, Parser, Query, QueryCursor};
+
+use crate::{cells, language, CellKind, TAGS_QUERY};
+
+/// The kind of a notebook [`Symbol`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    /// A cell itself, named after its declared language (or kind, if none).
+    Cell,
+    /// A markdown section heading inside a cell body.
+    Heading,
+    /// A `def`/`class`/function-style definition inside a code cell body,
+    /// as reported by that language's own tags query.
+    Definition,
+}
+
+/// A single navigable symbol in a notebook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub kind: SymbolKind,
+    pub name: String,
+    pub byte_range: Range<usize>,
+}
+
+/// Resolves a cell's declared language tag to the tree-sitter `Language` and
+/// `tags.scm` that know how to find definitions and headings in it. The
+/// notebook grammar has no idea what a Python `def` or an R-markdown heading
+/// looks like, so pulling in a language's own tags query is always delegated
+/// to the caller.
+pub trait TagsResolver {
+    fn resolve(&self, language_tag: &str) -> Option<(Language, &str)>;
+}
+
+impl<F> TagsResolver for F
+where
+    F: Fn(&str) -> Option<(Language, &'static str)>,
+{
+    fn resolve(&self, language_tag: &str) -> Option<(Language, &str)> {
+        self(language_tag)
+    }
+}
+
+struct NoDelegation;
+
+impl TagsResolver for NoDelegation {
+    fn resolve(&self, _language_tag: &str) -> Option<(Language, &str)> {
+        None
+    }
+}
+
+/// List the symbols in `source`, one per cell, without delegating into any
+/// injected language's own definitions.
+pub fn symbols(source: &str) -> Vec<Symbol> {
+    symbols_with(source, &NoDelegation)
+}
+
+/// List the symbols in `source`, delegating into an injected language's own
+/// tags query (via `resolver`) for markdown headings and code definitions.
+pub fn symbols_with(source: &str, resolver: &dyn TagsResolver) -> Vec<Symbol> {
+    let mut out = cell_symbols(source);
+
+    for cell in cells(source) {
+        let kind = match cell.kind {
+            CellKind::Markdown => SymbolKind::Heading,
+            CellKind::Code => SymbolKind::Definition,
+            CellKind::Raw => continue,
+        };
+        let is_markdown = cell.kind == CellKind::Markdown;
+        let tag = cell
+            .language
+            .or_else(|| is_markdown.then(|| "markdown".to_owned()));
+        let Some(tag) = tag else { continue };
+        let Some((injected_language, injected_tags)) = resolver.resolve(&tag) else {
+            continue;
+        };
+
+        let body_offset = cell.byte_range.end - cell.source.len();
+        out.extend(delegated_symbols(
+            injected_language,
+            injected_tags,
+            &cell.source,
+            body_offset,
+            kind,
+        ));
+    }
+
+    out
+}
+
+fn cell_symbols(source: &str) -> Vec<Symbol> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(language())
+        .expect("Error loading Notebook grammar");
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+
+    let query = Query::new(language(), TAGS_QUERY).expect("tags.scm should be a valid query");
+    let name_idx = query.capture_index_for_name("name");
+    let context_idx = query.capture_index_for_name("context").unwrap();
+    let definition_idx = query.capture_index_for_name("definition.cell").unwrap();
+
+    let bytes = source.as_bytes();
+    let mut cursor = QueryCursor::new();
+    let mut out = Vec::new();
+    for m in cursor.matches(&query, tree.root_node(), bytes) {
+        let Some(definition) = m.captures.iter().find(|c| c.index == definition_idx) else {
+            continue;
+        };
+        let name = name_idx
+            .and_then(|idx| m.captures.iter().find(|c| c.index == idx))
+            .or_else(|| m.captures.iter().find(|c| c.index == context_idx))
+            .and_then(|c| c.node.utf8_text(bytes).ok())
+            .unwrap_or("cell")
+            .to_owned();
+
+        out.push(Symbol {
+            kind: SymbolKind::Cell,
+            name,
+            byte_range: definition.node.byte_range(),
+        });
+    }
+    out
+}
+
+fn delegated_symbols(
+    language: Language,
+    tags_query: &str,
+    text: &str,
+    offset: usize,
+    kind: SymbolKind,
+) -> Vec<Symbol> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(language)
+        .expect("Error loading injected grammar");
+    let Some(tree) = parser.parse(text, None) else {
+        return Vec::new();
+    };
+    let query = match Query::new(language, tags_query) {
+        Ok(query) => query,
+        Err(_) => return Vec::new(),
+    };
+    let Some(name_idx) = query.capture_index_for_name("name") else {
+        return Vec::new();
+    };
+
+    let bytes = text.as_bytes();
+    let mut cursor = QueryCursor::new();
+    let mut out = Vec::new();
+    for m in cursor.matches(&query, tree.root_node(), bytes) {
+        for capture in m.captures.iter().filter(|c| c.index == name_idx) {
+            let Ok(name) = capture.node.utf8_text(bytes) else {
+                continue;
+            };
+            let range = capture.node.byte_range();
+            out.push(Symbol {
+                kind,
+                name: name.to_owned(),
+                byte_range: offset + range.start..offset + range.end,
+            });
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbols_one_per_cell() {
+        let source = "%%markdown\nintro\n\n%%code python\nimport sys\n";
+        let syms = symbols(source);
+        assert_eq!(syms.len(), 2);
+        assert_eq!(syms[0].kind, SymbolKind::Cell);
+        assert_eq!(syms[0].name, "markdown");
+        assert_eq!(syms[1].kind, SymbolKind::Cell);
+        assert_eq!(syms[1].name, "python");
+    }
+
+    #[test]
+    fn test_symbols_with_no_resolver_falls_back_to_cell_symbols_only() {
+        let source = "%%code bash\necho hi\n";
+        assert_eq!(symbols(source), symbols_with(source, &NoDelegation));
+    }
+
+    // `delegated_symbols` is what actually produces `Heading`/`Definition`
+    // symbols for a cell's body; exercise it directly against a small,
+    // self-contained tree rather than relying on a bundled second grammar
+    // this crate doesn't have a dependency on.
+    #[test]
+    fn test_delegated_symbols_reports_heading_kind_with_translated_offsets() {
+        let text = "%%code intro\n";
+        let offset = 100;
+        let found = delegated_symbols(language(), TAGS_QUERY, text, offset, SymbolKind::Heading);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, SymbolKind::Heading);
+        assert_eq!(found[0].name, "intro");
+        assert_eq!(found[0].byte_range, offset + 7..offset + 12);
+    }
+
+    #[test]
+    fn test_delegated_symbols_reports_definition_kind() {
+        let text = "%%code helper\n";
+        let found = delegated_symbols(language(), TAGS_QUERY, text, 0, SymbolKind::Definition);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, SymbolKind::Definition);
+        assert_eq!(found[0].name, "helper");
+    }
+}